@@ -1,15 +1,21 @@
 use anyhow::{Result, anyhow};
 use clap::{Parser, Subcommand};
+use globset::{Glob, GlobSetBuilder};
 use log::{debug, error, info};
 use serde::{Deserialize, Serialize};
+use semver::{BuildMetadata, Prerelease, Version};
 use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::fmt::Display;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
 mod graph;
-mod workspace;
+mod project;
+
+use graph::NodeIndex;
+use project::Project;
 
 #[derive(Parser)]
 #[command(name = "changement")]
@@ -46,6 +52,45 @@ enum Command {
         #[arg(short, long, default_value = "minor")]
         bump: VersionBump,
     },
+
+    /// Apply all changelog entries and update package versions
+    Version {
+        /// The path to the project directory
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Produce snapshot prereleases tagged with the given name (e.g.
+        /// `--snapshot canary` yields versions like `1.2.0-canary.0`)
+        #[arg(long, visible_alias = "pre")]
+        snapshot: Option<String>,
+    },
+
+    /// Publish updated packages to the registry in dependency order
+    Publish {
+        /// The path to the project directory
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Print the publish plan without publishing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Only consider workspaces affected by files changed since this git
+        /// ref (e.g. `origin/main`), rather than every non-ignored workspace
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Create git tags for the current package versions
+    Tag {
+        /// The path to the project directory
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Print the tags that would be created without creating them
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, clap::ValueEnum, Eq, PartialEq)]
@@ -61,6 +106,79 @@ enum VersionBump {
     Patch,
 }
 
+impl VersionBump {
+    /// Returns the higher-priority bump of the two (Major > Minor > Patch).
+    fn max(self, other: VersionBump) -> VersionBump {
+        if self.rank() >= other.rank() { self } else { other }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            VersionBump::Major => 2,
+            VersionBump::Minor => 1,
+            VersionBump::Patch => 0,
+        }
+    }
+
+    /// The CHANGELOG.md section the bodies of this bump are bucketed under.
+    fn heading(&self) -> &'static str {
+        match self {
+            VersionBump::Major => "Major Changes",
+            VersionBump::Minor => "Minor Changes",
+            VersionBump::Patch => "Patch Changes",
+        }
+    }
+
+    /// Applies the bump to `current`, returning the next stable version with any
+    /// prerelease or build metadata cleared.
+    fn apply(&self, current: &Version) -> Version {
+        let mut next = current.clone();
+        match self {
+            VersionBump::Major => {
+                next.major += 1;
+                next.minor = 0;
+                next.patch = 0;
+            }
+            VersionBump::Minor => {
+                next.minor += 1;
+                next.patch = 0;
+            }
+            VersionBump::Patch => {
+                next.patch += 1;
+            }
+        }
+        next.pre = Prerelease::EMPTY;
+        next.build = BuildMetadata::EMPTY;
+        next
+    }
+
+    /// Produces a snapshot prerelease of the bump under `tag`, e.g.
+    /// `1.2.0-canary.0`. When `current` is already a snapshot of the same base
+    /// and tag, only the numeric suffix is incremented so repeated runs don't
+    /// advance the stable version line.
+    fn apply_snapshot(&self, current: &Version, tag: &str) -> Result<Version> {
+        let prefix = format!("{tag}.");
+        let continues = current
+            .pre
+            .as_str()
+            .strip_prefix(&prefix)
+            .and_then(|suffix| suffix.parse::<u64>().ok());
+
+        let (mut base, suffix) = match continues {
+            Some(previous) => {
+                let mut base = current.clone();
+                base.pre = Prerelease::EMPTY;
+                base.build = BuildMetadata::EMPTY;
+                (base, previous + 1)
+            }
+            None => (self.apply(current), 0),
+        };
+
+        base.pre = Prerelease::new(&format!("{tag}.{suffix}"))?;
+        Ok(base)
+    }
+}
+
 impl Display for VersionBump {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -99,6 +217,13 @@ fn process(cwd: PathBuf, command: &Command) -> Result<()> {
             message,
             bump,
         } => new_command(cwd, package, message, bump),
+        Command::Version { path, snapshot } => version_command(cwd, path, snapshot.as_deref()),
+        Command::Publish {
+            path,
+            dry_run,
+            since,
+        } => publish_command(cwd, path, *dry_run, since.as_deref()),
+        Command::Tag { path, dry_run } => tag_command(cwd, path, *dry_run),
     }
 }
 
@@ -165,6 +290,378 @@ fn new_command(cwd: PathBuf, package: &str, message: &str, bump: &VersionBump) -
     Ok(())
 }
 
+fn version_command(cwd: PathBuf, path: &str, snapshot: Option<&str>) -> Result<()> {
+    let root = cwd.join(path);
+    let changelog_dir = root.join(".changelog");
+    let config = Config::load(&changelog_dir)?;
+
+    // Validate the snapshot tag once so a malformed tag fails before anything
+    // is written rather than part-way through the run.
+    if let Some(tag) = snapshot {
+        Prerelease::new(&format!("{tag}.0"))
+            .map_err(|e| anyhow!("Invalid snapshot tag '{tag}': {e}"))?;
+    }
+
+    // Collect and parse every pending changelog entry.
+    let mut entry_paths: Vec<PathBuf> = Vec::new();
+    let mut entries: Vec<ChangelogEntry> = Vec::new();
+    for entry in fs::read_dir(&changelog_dir)? {
+        let entry_path = entry?.path();
+        if entry_path.extension() == Some(OsStr::new("md")) {
+            let contents = fs::read_to_string(&entry_path)?;
+            entries.push(ChangelogEntry::from_string(contents)?);
+            entry_paths.push(entry_path);
+        }
+    }
+
+    if entries.is_empty() {
+        info!("No changelog entries to apply");
+        return Ok(());
+    }
+
+    // Aggregate the pending changes per package: the highest bump wins, and the
+    // bodies are kept so they can be bucketed by bump in the changelog.
+    let mut bumps: HashMap<String, VersionBump> = HashMap::new();
+    let mut changes: HashMap<String, Vec<(VersionBump, String)>> = HashMap::new();
+    for entry in &entries {
+        for (package, bump) in &entry.frontmatter {
+            bumps
+                .entry(package.clone())
+                .and_modify(|current| *current = current.clone().max(bump.clone()))
+                .or_insert_with(|| bump.clone());
+            changes
+                .entry(package.clone())
+                .or_default()
+                .push((bump.clone(), entry.body.clone()));
+        }
+    }
+
+    // Packages in a `fixed` group release together: if any member changed,
+    // every member is bumped so they all land on the same version.
+    for group in &config.fixed {
+        if group.iter().any(|member| bumps.contains_key(member)) {
+            for member in group {
+                bumps.entry(member.clone()).or_insert(VersionBump::Patch);
+            }
+        }
+    }
+
+    let project = Project::load_cached(&root)?;
+    let ignore = ignore_set(&config.ignore);
+
+    // Looks up a bumpable workspace by package name: `None` both when no local
+    // workspace has that name and when it's excluded by `Config.ignore`.
+    let resolve = |name: &str| -> Option<NodeIndex> {
+        let (node, _) = project.get_workspace(name)?;
+        resolve_name(&project, node, &ignore)?;
+        Some(node)
+    };
+
+    // Cascade each bump to its dependents, giving every impacted consumer at
+    // least a patch bump so it is re-released against the new dependency.
+    let mut worklist: Vec<String> = bumps.keys().cloned().collect();
+    while let Some(package) = worklist.pop() {
+        // A `fixed` group also moves together when a member is bumped only
+        // through this cascade.
+        for group in config.fixed.iter().filter(|group| group.contains(&package)) {
+            for member in group {
+                if !bumps.contains_key(member) {
+                    bumps.insert(member.clone(), VersionBump::Patch);
+                    worklist.push(member.clone());
+                }
+            }
+        }
+        let Some(node) = resolve(&package) else {
+            continue;
+        };
+        for dependent in project.dependents(node) {
+            let Some(name) = resolve_name(&project, dependent, &ignore) else {
+                continue;
+            };
+            if !bumps.contains_key(&name) {
+                bumps.insert(name.clone(), VersionBump::Patch);
+                worklist.push(name);
+            }
+        }
+    }
+
+    // Resolve the new version for every bumped package up front so dependents'
+    // ranges can be rewritten to point at them.
+    let mut new_versions: HashMap<String, String> = HashMap::new();
+    for (package, bump) in &bumps {
+        let Some(node) = resolve(package) else {
+            error!("No workspace found for package '{package}', skipping");
+            continue;
+        };
+        let Some(current) = project.workspace(node).and_then(|node| node.data.version()) else {
+            error!("Package '{package}' has no version field, skipping");
+            continue;
+        };
+        let next = match snapshot {
+            Some(tag) => bump.apply_snapshot(current, tag)?,
+            None => bump.apply(current),
+        };
+        new_versions.insert(package.clone(), next.to_string());
+    }
+
+    // Pin grouped packages to a shared version: `fixed` groups move every
+    // member (all present after the injection above), `linked` groups move only
+    // the members that actually changed.
+    for group in &config.fixed {
+        raise_to_max(group, &mut new_versions);
+    }
+    for group in &config.linked {
+        let changed: Vec<String> = group
+            .iter()
+            .filter(|member| new_versions.contains_key(*member))
+            .cloned()
+            .collect();
+        raise_to_max(&changed, &mut new_versions);
+    }
+
+    for (package, next) in &new_versions {
+        let Some(node) = resolve(package) else {
+            continue;
+        };
+        let Some(workspace) = project.workspace(node) else {
+            continue;
+        };
+        let directory = workspace.data.directory().to_path_buf();
+        let next_version = Version::parse(next)?;
+
+        // Write the new version as a minimal textual substitution so keys the
+        // typed model doesn't capture (scripts, exports, publishConfig, ...)
+        // and the manifest's original formatting survive untouched.
+        project.write_version(node, &next_version)?;
+
+        // Propagate the new version into every local dependent's manifest,
+        // preserving each range's operator and the `workspace:` protocol.
+        project.rewrite_dependents(node, &next_version)?;
+
+        // A cascaded dependent has no changelog bodies of its own; note the
+        // dependency update so the release is still documented.
+        let mut bodies = changes.get(package).cloned().unwrap_or_default();
+        if bodies.is_empty() {
+            bodies.push((VersionBump::Patch, "Updated local dependencies.".to_string()));
+        }
+        prepend_changelog(&directory, next, &bodies)?;
+        info!("{package} -> {next}");
+    }
+
+    // Snapshot releases are ephemeral canary builds, so the entries are left in
+    // place to feed the eventual stable release. Only a real release consumes
+    // them.
+    if snapshot.is_none() {
+        for entry_path in &entry_paths {
+            fs::remove_file(entry_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The package name of `node`, or `None` when it's unnamed or excluded by
+/// `ignore`.
+fn resolve_name(project: &Project, node: NodeIndex, ignore: &globset::GlobSet) -> Option<String> {
+    let name = project.workspace(node)?.data.name.as_deref()?;
+    (!ignore.is_match(name)).then(|| name.to_string())
+}
+
+fn publish_command(cwd: PathBuf, path: &str, dry_run: bool, since: Option<&str>) -> Result<()> {
+    let root = cwd.join(path);
+    let config = Config::load(&root.join(".changelog"))?;
+    let project = Project::load_cached(&root)?;
+
+    // When `--since` is given, narrow the release set to workspaces affected
+    // by files changed since that ref, fanned out through local dependents.
+    let affected = match since {
+        Some(since) => Some(project.affected(changed_files_since(&root, since)?)),
+        None => None,
+    };
+
+    // Release every non-ignored (and, if `--since` is set, affected) workspace,
+    // ordered so a package is published only after the local dependencies it
+    // relies on.
+    let ignore = ignore_set(&config.ignore);
+    let roots: Vec<NodeIndex> = project
+        .get_workspaces()
+        .filter(|(_, node)| match &node.data.name {
+            Some(name) => !ignore.is_match(name),
+            None => true,
+        })
+        .filter(|(index, _)| match &affected {
+            Some(affected) => affected.contains(index),
+            None => true,
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    for batch in project.release_order(roots)? {
+        for node in batch {
+            let Some(workspace) = project.workspace(node) else {
+                continue;
+            };
+            let (Some(name), Some(version)) =
+                (workspace.data.name.as_deref(), workspace.data.version())
+            else {
+                continue;
+            };
+            let version = version.to_string();
+
+            if dry_run {
+                info!("Would publish {name}@{version}");
+                continue;
+            }
+
+            if is_published(name, &version)? {
+                info!("{name}@{version} already published, skipping");
+                continue;
+            }
+
+            publish(workspace.data.directory())?;
+            info!("Published {name}@{version}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds a `GlobSet` from `patterns`, used to match package names against the
+/// configured `ignore` list. Invalid patterns are reported and skipped.
+fn ignore_set(patterns: &[String]) -> globset::GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => error!("Invalid ignore pattern '{pattern}': {e}"),
+        }
+    }
+    builder.build().unwrap_or_else(|_| globset::GlobSet::empty())
+}
+
+/// The paths changed since `since` (a git ref such as a branch or tag),
+/// resolved relative to `root`.
+fn changed_files_since(root: &Path, since: &str) -> Result<Vec<PathBuf>> {
+    let output = std::process::Command::new("git")
+        .args(["diff", "--name-only", since])
+        .current_dir(root)
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow!("failed to diff against '{since}'"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| root.join(line))
+        .collect())
+}
+
+/// Whether `name@version` already exists on the configured npm registry.
+fn is_published(name: &str, version: &str) -> Result<bool> {
+    let output = std::process::Command::new("npm")
+        .args(["view", &format!("{name}@{version}"), "version"])
+        .output()?;
+    Ok(output.status.success() && !output.stdout.is_empty())
+}
+
+fn publish(directory: &Path) -> Result<()> {
+    let status = std::process::Command::new("npm")
+        .arg("publish")
+        .current_dir(directory)
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("npm publish failed in {}", directory.display()));
+    }
+    Ok(())
+}
+
+fn tag_command(cwd: PathBuf, path: &str, dry_run: bool) -> Result<()> {
+    let root = cwd.join(path);
+    let config = Config::load(&root.join(".changelog"))?;
+    let project = Project::load_cached(&root)?;
+    let ignore = ignore_set(&config.ignore);
+
+    for (_, workspace) in project.get_workspaces() {
+        let (Some(name), Some(version)) = (workspace.data.name.as_deref(), workspace.data.version())
+        else {
+            continue;
+        };
+        if ignore.is_match(name) {
+            continue;
+        }
+        let tag = format!("{name}@{version}");
+
+        if tag_exists(project.directory(), &tag)? {
+            info!("Tag {tag} already exists, skipping");
+            continue;
+        }
+
+        if dry_run {
+            info!("Would create tag {tag}");
+            continue;
+        }
+
+        create_tag(project.directory(), &tag)?;
+        info!("Created tag {tag}");
+    }
+
+    Ok(())
+}
+
+/// Whether an annotated or lightweight tag named `tag` already exists.
+fn tag_exists(root: &Path, tag: &str) -> Result<bool> {
+    let output = std::process::Command::new("git")
+        .args(["tag", "--list", tag])
+        .current_dir(root)
+        .output()?;
+    Ok(output.status.success() && !output.stdout.is_empty())
+}
+
+fn create_tag(root: &Path, tag: &str) -> Result<()> {
+    let status = std::process::Command::new("git")
+        .args(["tag", "-a", tag, "-m", tag])
+        .current_dir(root)
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("failed to create git tag {tag}"));
+    }
+    Ok(())
+}
+
+fn prepend_changelog(
+    directory: &Path,
+    version: &str,
+    changes: &[(VersionBump, String)],
+) -> Result<()> {
+    let mut section = format!("## {version}\n");
+    for bump in [VersionBump::Major, VersionBump::Minor, VersionBump::Patch] {
+        let mut bodies = changes
+            .iter()
+            .filter(|(b, _)| *b == bump)
+            .map(|(_, body)| body)
+            .peekable();
+        if bodies.peek().is_none() {
+            continue;
+        }
+        section.push_str(&format!("\n### {}\n\n", bump.heading()));
+        for body in bodies {
+            section.push_str(&format!("- {body}\n"));
+        }
+    }
+
+    let changelog_path = directory.join("CHANGELOG.md");
+    let existing = fs::read_to_string(&changelog_path).unwrap_or_default();
+    let contents = if existing.is_empty() {
+        section
+    } else {
+        format!("{section}\n{existing}")
+    };
+    fs::write(&changelog_path, contents)?;
+
+    Ok(())
+}
+
 struct ChangelogEntry {
     frontmatter: HashMap<String, VersionBump>,
     body: String,
@@ -176,7 +673,6 @@ impl ChangelogEntry {
         Ok(format!("---\n{}---\n\n{}", frontmatter_yaml, self.body))
     }
 
-    #[allow(dead_code)]
     fn from_string(contents: String) -> Result<Self, serde_yml::Error> {
         let parts: Vec<&str> = contents.trim().split("---").collect();
         if parts.len() < 3 {
@@ -194,7 +690,50 @@ impl ChangelogEntry {
 
 #[derive(Serialize, Deserialize, Default, Debug, Eq, PartialEq)]
 struct Config {
+    #[serde(default)]
     ignore: Vec<String>,
+
+    /// Groups of packages that are versioned in lockstep: whenever any member
+    /// changes, every member is bumped and pinned to the group's highest
+    /// resulting version.
+    #[serde(default)]
+    fixed: Vec<Vec<String>>,
+
+    /// Groups of packages whose changed members are raised to the highest
+    /// version among them, leaving untouched members alone.
+    #[serde(default)]
+    linked: Vec<Vec<String>>,
+}
+
+impl Config {
+    /// Loads `.changelog/config.yml` from `changelog_dir`, falling back to the
+    /// defaults when no config file is present.
+    fn load(changelog_dir: &Path) -> Result<Config> {
+        let config_path = changelog_dir.join("config.yml");
+        match fs::read_to_string(&config_path) {
+            Ok(contents) => Ok(serde_yml::from_str(&contents)?),
+            Err(_) => Ok(Config::default()),
+        }
+    }
+}
+
+/// Raises every member of `members` that has a pending version to the highest
+/// pending version in the group, keeping grouped packages in step.
+fn raise_to_max(members: &[String], new_versions: &mut HashMap<String, String>) {
+    let max = members
+        .iter()
+        .filter_map(|member| new_versions.get(member))
+        .filter_map(|version| Version::parse(version).ok())
+        .max();
+    let Some(max) = max else {
+        return;
+    };
+    let max = max.to_string();
+    for member in members {
+        if new_versions.contains_key(member) {
+            new_versions.insert(member.clone(), max.clone());
+        }
+    }
 }
 
 #[cfg(test)]
@@ -237,6 +776,50 @@ mod tests {
         assert_eq!(config, Config::default());
     }
 
+    #[test]
+    fn test_version_bump_apply() {
+        assert_eq!(
+            VersionBump::Major.apply(&Version::parse("1.2.3").unwrap()),
+            Version::parse("2.0.0").unwrap()
+        );
+        assert_eq!(
+            VersionBump::Minor.apply(&Version::parse("1.2.3").unwrap()),
+            Version::parse("1.3.0").unwrap()
+        );
+        assert_eq!(
+            VersionBump::Patch.apply(&Version::parse("1.2.3-rc.1").unwrap()),
+            Version::parse("1.2.4").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_version_bump_apply_snapshot() -> Result<()> {
+        let first = VersionBump::Minor.apply_snapshot(&Version::parse("1.1.0")?, "canary")?;
+        assert_eq!(first, Version::parse("1.2.0-canary.0")?);
+
+        let second = VersionBump::Minor.apply_snapshot(&first, "canary")?;
+        assert_eq!(second, Version::parse("1.2.0-canary.1")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_raise_to_max() {
+        let mut new_versions = HashMap::from([
+            ("a".to_string(), "1.2.0".to_string()),
+            ("b".to_string(), "1.5.0".to_string()),
+            ("c".to_string(), "0.1.0".to_string()),
+        ]);
+        raise_to_max(
+            &["a".to_string(), "b".to_string(), "missing".to_string()],
+            &mut new_versions,
+        );
+        assert_eq!(new_versions["a"], "1.5.0");
+        assert_eq!(new_versions["b"], "1.5.0");
+        // A package outside the group keeps its own computed version.
+        assert_eq!(new_versions["c"], "0.1.0");
+    }
+
     #[test]
     fn test_new_command() -> Result<()> {
         let temp_dir = TempDir::new().unwrap();
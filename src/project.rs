@@ -1,72 +1,119 @@
 use crate::graph::{Direction, Graph, Node, NodeIndex};
-use globset::{Glob, GlobSetBuilder};
+use anyhow::{Result, anyhow};
+use globset::{GlobBuilder, GlobSetBuilder};
 use ignore::Walk;
 use semver::Version;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
 use std::str::FromStr;
 use std::{collections::HashMap, path::PathBuf};
 
 pub struct Project {
     directory: PathBuf,
+    /// Filesystem containment: an edge runs between a parent workspace and the
+    /// child workspaces matched by its `workspaces` globs.
     graph: Graph<Workspace>,
+    /// Package dependency relation: an `Outgoing` edge runs from a dependent to
+    /// each local workspace it depends on. Node indices mirror `graph`.
+    dependency_graph: Graph<()>,
 }
 
 impl Project {
-    pub fn new(directory: impl AsRef<Path>) -> Self {
+    /// Builds a `Project`, reusing manifests from the on-disk graph cache at
+    /// `.changement/graph.json` whose content hash is unchanged and only
+    /// re-parsing the `package.json` files that are new or modified. The
+    /// refreshed cache is written back, so adding or removing a manifest
+    /// transparently invalidates the stale entry.
+    pub fn load_cached(directory: impl AsRef<Path>) -> Result<Self> {
+        let directory = directory.as_ref();
+        let cache_path = directory.join(".changement").join("graph.json");
+
+        let cached: HashMap<PathBuf, CachedWorkspace> = std::fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<GraphCache>(&contents).ok())
+            .map(|cache| {
+                cache
+                    .workspaces
+                    .into_iter()
+                    .map(|entry| (entry.path.clone(), entry))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut manifests: Vec<(PathBuf, PackageJson)> = Vec::new();
+        let mut entries: Vec<CachedWorkspace> = Vec::new();
+        let mut visited: HashSet<PathBuf> = HashSet::new();
+        let mut queue: Vec<PathBuf> = vec![directory.to_path_buf()];
+
+        // Discovery follows the containment tree rooted at `directory`: a
+        // workspace's own `workspaces` globs (or pnpm-workspace.yaml) decide
+        // which subdirectories are visited next, so a `package.json` under
+        // `vendor/` or `node_modules/` is never picked up as a workspace.
+        while let Some(workspace_dir) = queue.pop() {
+            if !visited.insert(workspace_dir.clone()) {
+                continue;
+            }
+
+            let path = workspace_dir.join("package.json");
+            let Ok(bytes) = std::fs::read(&path) else {
+                continue;
+            };
+            let hash = content_hash(&bytes);
+            let package_json = match cached.get(&path) {
+                Some(entry) if entry.hash == hash => entry.package_json.clone(),
+                _ => serde_json::from_slice::<PackageJson>(&bytes)?,
+            };
+            entries.push(CachedWorkspace {
+                path: path.clone(),
+                hash,
+                package_json: package_json.clone(),
+            });
+
+            let patterns = workspace_patterns(&workspace_dir, &package_json);
+            manifests.push((path, package_json));
+            queue.extend(matched_workspace_dirs(&workspace_dir, &patterns));
+        }
+
+        if let Some(parent) = cache_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(
+            &cache_path,
+            serde_json::to_string_pretty(&GraphCache { workspaces: entries })?,
+        )?;
+
+        Ok(Self::build(directory, manifests))
+    }
+
+    /// Constructs the containment and dependency graphs from already-parsed
+    /// `manifests` (each a `package.json` path and its parsed contents).
+    fn build(directory: impl AsRef<Path>, manifests: Vec<(PathBuf, PackageJson)>) -> Self {
         let mut workspaces: Vec<(NodeIndex, Vec<String>)> = Vec::new();
         let mut graph = Graph::new();
-        let walker = Walk::new(&directory);
 
-        for result in walker.filter_map(|e| e.ok()) {
-            if let Some(file_type) = result.file_type()
-                && file_type.is_file()
-                && result.file_name() == "package.json"
-            {
-                let package_json_path = result.path();
-                let content = std::fs::read_to_string(package_json_path)
-                    .expect("Failed to read package.json file");
-                let package_json = serde_json::from_str::<PackageJson>(&content)
-                    .expect("Failed to parse package.json");
-                let workspace = Workspace::new(package_json_path, &package_json);
-                let node_index = graph.add_node(workspace);
-                workspaces.push((node_index, package_json.workspaces));
-            }
+        for (package_json_path, package_json) in &manifests {
+            let directory = package_json_path
+                .parent()
+                .expect("package.json should have a parent directory");
+            let patterns = workspace_patterns(directory, package_json);
+            let workspace = Workspace::new(directory, package_json);
+            let node_index = graph.add_node(workspace);
+            workspaces.push((node_index, patterns));
         }
 
         for (node_index, child_workspace_patterns) in &workspaces {
             let workspace = graph
                 .get_node(*node_index)
                 .expect("Node index should exist in the graph");
-            let mut builder = GlobSetBuilder::new();
 
-            for pattern in child_workspace_patterns {
-                let glob = Glob::new(&pattern).expect("Invalid glob pattern");
-                builder.add(glob);
-            }
-
-            let set = builder.build().expect("Failed to build GlobSet");
-            let walker = Walk::new(&workspace.data.directory)
-                .filter_map(|e| e.ok())
-                .filter(|e| set.is_match(e.path()))
-                .filter(|e| {
-                    if let Some(file_type) = e.file_type()
-                        && file_type.is_dir()
-                        && e.path().join("package.json").exists()
-                    {
-                        true
-                    } else {
-                        false
-                    }
-                });
-
-            for directory in walker {
-                let child_workspace = &workspaces.iter().find_map(|(child_workspace_index, _)| {
+            for directory in matched_workspace_dirs(&workspace.data.directory, child_workspace_patterns) {
+                let child_workspace = workspaces.iter().find_map(|(child_workspace_index, _)| {
                     let child_workspace = graph
                         .get_node(*child_workspace_index)
                         .expect("Child workspace node should exist in the graph");
 
-                    if child_workspace.data.directory == directory.path() {
+                    if child_workspace.data.directory == directory {
                         Some(*child_workspace_index)
                     } else {
                         None
@@ -74,18 +121,60 @@ impl Project {
                 });
 
                 if let Some(child_workspace_index) = child_workspace {
-                    graph.add_edge(*node_index, *child_workspace_index, Direction::Incoming);
-                    graph.add_edge(*child_workspace_index, *node_index, Direction::Outgoing);
+                    graph.add_edge(*node_index, child_workspace_index, Direction::Incoming);
+                    graph.add_edge(child_workspace_index, *node_index, Direction::Outgoing);
+                }
+            }
+        }
+
+        // Build the package dependency relation as a separate graph so callers
+        // can distinguish "is nested under" (containment) from "depends on."
+        let mut dependency_graph: Graph<()> = Graph::new();
+        for _ in 0..workspaces.len() {
+            dependency_graph.add_node(());
+        }
+
+        let mut name_to_index: HashMap<String, NodeIndex> = HashMap::new();
+        for (index, node) in graph.get_nodes() {
+            if let Some(name) = &node.data.name {
+                name_to_index.insert(name.clone(), index);
+            }
+        }
+
+        let mut dependency_edges: Vec<(NodeIndex, NodeIndex)> = Vec::new();
+        for (index, node) in graph.get_nodes() {
+            for (name, dependency) in &node.data.dependencies {
+                let Some(&dependency_index) = name_to_index.get(name) else {
+                    continue;
+                };
+                let Some(node) = graph.get_node(dependency_index) else {
+                    continue;
+                };
+                if let Some(version) = &node.data.version
+                    && dependency.matches(version)
+                {
+                    dependency_edges.push((index, dependency_index));
                 }
             }
         }
 
+        for (dependent, dependency) in dependency_edges {
+            dependency_graph.add_edge(dependent, dependency, Direction::Outgoing);
+            dependency_graph.add_edge(dependency, dependent, Direction::Incoming);
+        }
+
         Self {
             directory: directory.as_ref().to_path_buf(),
             graph,
+            dependency_graph,
         }
     }
 
+    /// The root directory this project was built from.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
     pub fn get_workspaces(&self) -> impl Iterator<Item = (NodeIndex, &Node<Workspace>)> {
         self.graph.get_nodes()
     }
@@ -101,12 +190,335 @@ impl Project {
     }
 
     pub fn dependents(&self, workspace: NodeIndex) -> impl Iterator<Item = NodeIndex> {
-        self.graph.edges(workspace, Direction::Incoming)
+        self.dependency_graph.edges(workspace, Direction::Incoming)
     }
 
     pub fn dependencies(&self, workspace: NodeIndex) -> impl Iterator<Item = NodeIndex> {
-        self.graph.edges(workspace, Direction::Outgoing)
+        self.dependency_graph.edges(workspace, Direction::Outgoing)
+    }
+
+    /// The set of workspaces impacted by `changed_files`: each path is attributed
+    /// to the innermost workspace that contains it, then the set is closed over
+    /// the dependency relation so every downstream consumer is included too.
+    /// Paths that fall outside every workspace directory are ignored.
+    pub fn affected(&self, changed_files: impl IntoIterator<Item = PathBuf>) -> HashSet<NodeIndex> {
+        let mut affected: HashSet<NodeIndex> = HashSet::new();
+
+        for file in changed_files {
+            // The owning workspace is the one whose directory is the longest
+            // prefix of the file, so a file in a nested workspace attributes to
+            // the innermost one.
+            let owner = self
+                .graph
+                .get_nodes()
+                .filter(|(_, node)| file.starts_with(&node.data.directory))
+                .max_by_key(|(_, node)| node.data.directory.as_os_str().len())
+                .map(|(index, _)| index);
+
+            if let Some(owner) = owner {
+                affected.insert(owner);
+            }
+        }
+
+        // Fan out through dependents so consumers of a changed workspace are
+        // flagged for release as well.
+        let mut worklist: Vec<NodeIndex> = affected.iter().copied().collect();
+        while let Some(node) = worklist.pop() {
+            for dependent in self.dependents(node) {
+                if affected.insert(dependent) {
+                    worklist.push(dependent);
+                }
+            }
+        }
+
+        affected
+    }
+
+    /// Writes `new_version` into `workspace`'s own manifest as a minimal
+    /// textual substitution, so keys the typed model doesn't capture (scripts,
+    /// exports, publishConfig, ...) and the manifest's original formatting
+    /// survive untouched. A no-op if the workspace has no `version` field or
+    /// already matches.
+    pub fn write_version(&self, workspace: NodeIndex, new_version: &Version) -> Result<()> {
+        let Some(node) = self.graph.get_node(workspace) else {
+            return Ok(());
+        };
+        let Some(current) = &node.data.version else {
+            return Ok(());
+        };
+
+        let manifest = node.data.directory.join("package.json");
+        let text = std::fs::read_to_string(&manifest)?;
+        if let Some(next) =
+            replace_json_string_value(&text, "version", &current.to_string(), &new_version.to_string())
+        {
+            std::fs::write(&manifest, next)?;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites every dependent's manifest to point at `workspace`'s
+    /// `new_version`, preserving the original range operator and the
+    /// `workspace:` protocol. Edits are applied in place as minimal textual
+    /// substitutions so key order and formatting survive, and only manifests
+    /// that actually changed are written back. Returns the rewritten paths.
+    pub fn rewrite_dependents(
+        &self,
+        workspace: NodeIndex,
+        new_version: &Version,
+    ) -> Result<Vec<PathBuf>> {
+        let Some(name) = self
+            .graph
+            .get_node(workspace)
+            .and_then(|node| node.data.name.clone())
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut rewritten = Vec::new();
+        for dependent in self.dependents(workspace).collect::<Vec<_>>() {
+            let Some(node) = self.graph.get_node(dependent) else {
+                continue;
+            };
+            let manifest = node.data.directory.join("package.json");
+            let mut text = std::fs::read_to_string(&manifest)?;
+            let value: serde_json::Value = serde_json::from_str(&text)?;
+
+            let mut edits: Vec<(String, String)> = Vec::new();
+            for section in ["dependencies", "devDependencies", "peerDependencies"] {
+                if let Some(range) = value
+                    .get(section)
+                    .and_then(|section| section.get(&name))
+                    .and_then(|range| range.as_str())
+                    && let Some(new_range) = rewrite_range(range, new_version)
+                {
+                    edits.push((range.to_string(), new_range));
+                }
+            }
+
+            if edits.is_empty() {
+                continue;
+            }
+
+            for (old, new) in edits {
+                if let Some(next) = replace_json_string_value(&text, &name, &old, &new) {
+                    text = next;
+                }
+            }
+
+            std::fs::write(&manifest, &text)?;
+            rewritten.push(manifest);
+        }
+
+        Ok(rewritten)
+    }
+
+    /// Orders `roots` into dependency waves: every workspace appears in a later
+    /// batch than all of its dependencies, and the workspaces within a batch
+    /// have no remaining dependencies among the requested set, so they can be
+    /// processed in parallel.
+    ///
+    /// Implemented with Kahn's algorithm over the dependency edges restricted to
+    /// `roots`. If the roots contain a dependency cycle the queue drains before
+    /// every node is emitted; the still-positive in-degree nodes are returned in
+    /// the error: the members of the cycle together with any requested
+    /// workspaces that depend on them.
+    pub fn release_order(
+        &self,
+        roots: impl IntoIterator<Item = NodeIndex>,
+    ) -> Result<Vec<Vec<NodeIndex>>> {
+        let nodes: HashSet<NodeIndex> = roots.into_iter().collect();
+
+        let mut in_degree: HashMap<NodeIndex, usize> = nodes
+            .iter()
+            .map(|&node| {
+                let degree = self
+                    .dependencies(node)
+                    .filter(|dependency| nodes.contains(dependency))
+                    .count();
+                (node, degree)
+            })
+            .collect();
+
+        let mut queue: Vec<NodeIndex> = in_degree
+            .iter()
+            .filter(|&(_, &degree)| degree == 0)
+            .map(|(&node, _)| node)
+            .collect();
+
+        let mut batches: Vec<Vec<NodeIndex>> = Vec::new();
+        let mut emitted = 0;
+        while !queue.is_empty() {
+            let batch = std::mem::take(&mut queue);
+            emitted += batch.len();
+            for &node in &batch {
+                for dependent in self.dependents(node) {
+                    if let Some(degree) = in_degree.get_mut(&dependent) {
+                        *degree -= 1;
+                        if *degree == 0 {
+                            queue.push(dependent);
+                        }
+                    }
+                }
+            }
+            batches.push(batch);
+        }
+
+        if emitted < nodes.len() {
+            let cycle: Vec<NodeIndex> = in_degree
+                .iter()
+                .filter(|&(_, &degree)| degree > 0)
+                .map(|(&node, _)| node)
+                .collect();
+            return Err(anyhow!(
+                "dependency cycle detected among workspaces: {cycle:?}"
+            ));
+        }
+
+        Ok(batches)
+    }
+}
+
+/// Reads the `workspaces` globs that `directory`'s manifest declares, merging
+/// in any sibling `pnpm-workspace.yaml` `packages:` list.
+fn workspace_patterns(directory: &Path, package_json: &PackageJson) -> Vec<String> {
+    let mut patterns: Vec<String> = package_json.workspaces.patterns().to_vec();
+    if let Ok(contents) = std::fs::read_to_string(directory.join("pnpm-workspace.yaml"))
+        && let Ok(pnpm) = serde_yml::from_str::<PnpmWorkspace>(&contents)
+    {
+        patterns.extend(pnpm.packages);
+    }
+    patterns
+}
+
+/// Resolves `patterns` (a leading `!` marks an exclusion, e.g.
+/// `!packages/internal-*`) against `directory`, returning the child
+/// directories that match and contain a `package.json`.
+fn matched_workspace_dirs(directory: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut builder = GlobSetBuilder::new();
+    let mut exclusions = GlobSetBuilder::new();
+
+    // `literal_separator` keeps a bare `*` from crossing `/`, so `packages/*`
+    // matches only direct children and not deeper nested paths such as a
+    // package's own `node_modules`.
+    for pattern in patterns {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            let glob = GlobBuilder::new(negated)
+                .literal_separator(true)
+                .build()
+                .expect("Invalid glob pattern");
+            exclusions.add(glob);
+        } else {
+            let glob = GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .expect("Invalid glob pattern");
+            builder.add(glob);
+        }
+    }
+
+    let set = builder.build().expect("Failed to build GlobSet");
+    let exclusions = exclusions.build().expect("Failed to build GlobSet");
+
+    Walk::new(directory)
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let relative = e.path().strip_prefix(directory).ok()?;
+            (set.is_match(relative) && !exclusions.is_match(relative)).then_some(e)
+        })
+        .filter(|e| {
+            if let Some(file_type) = e.file_type()
+                && file_type.is_dir()
+                && e.path().join("package.json").exists()
+            {
+                true
+            } else {
+                false
+            }
+        })
+        .map(|e| e.path().to_path_buf())
+        .collect()
+}
+
+/// Points a dependency range at `version`, preserving the leading range operator
+/// and the `workspace:` protocol. Returns `None` when the range carries no
+/// concrete version to rewrite — a bare `*`/`x` or a protocol alias such as
+/// `workspace:*`, `workspace:^`, `workspace:~`, which pnpm resolves at publish
+/// time — or when the range already matches.
+fn rewrite_range(range: &str, version: &Version) -> Option<String> {
+    if let Some(rest) = range.strip_prefix("workspace:") {
+        if matches!(rest, "" | "*" | "^" | "~") {
+            return None;
+        }
+        return rewrite_plain(rest, version).map(|rewritten| format!("workspace:{rewritten}"));
+    }
+    rewrite_plain(range, version)
+}
+
+fn rewrite_plain(range: &str, version: &Version) -> Option<String> {
+    // Only simple, single-comparator ranges are rewritten; compound ranges
+    // (`>=1 <2`), unions (`1 || 2`) and wildcards (`1.x`, `*`) are left as-is
+    // since their new form can't be derived from a single version.
+    if range.contains([' ', '|', 'x', 'X', '*']) {
+        return None;
     }
+    let operator: String = range.chars().take_while(|c| !c.is_ascii_digit()).collect();
+    if operator.len() == range.len() {
+        // No numeric component (e.g. `*`, `x`), so nothing to rewrite.
+        return None;
+    }
+    let rewritten = format!("{operator}{version}");
+    (rewritten != range).then_some(rewritten)
+}
+
+/// Replaces the first `"key": "old"` pair in `text` with `"key": "new"`,
+/// leaving the surrounding bytes untouched so the JSON formatting is preserved.
+fn replace_json_string_value(text: &str, key: &str, old: &str, new: &str) -> Option<String> {
+    let key = format!("\"{key}\"");
+    let mut search_from = 0;
+    while let Some(key_offset) = text[search_from..].find(&key) {
+        let after_key = search_from + key_offset + key.len();
+        let colon = after_key + text[after_key..].find(':')?;
+        let open = colon + 1 + text[colon + 1..].find('"')?;
+        let close = open + 1 + text[open + 1..].find('"')?;
+        if &text[open + 1..close] == old {
+            let mut result = String::with_capacity(text.len() + new.len());
+            result.push_str(&text[..open + 1]);
+            result.push_str(new);
+            result.push_str(&text[close..]);
+            return Some(result);
+        }
+        search_from = after_key;
+    }
+    None
+}
+
+/// The persisted project graph cache, one entry per discovered manifest.
+#[derive(Serialize, Deserialize, Default)]
+struct GraphCache {
+    workspaces: Vec<CachedWorkspace>,
+}
+
+/// A cached manifest: its `package.json` path, a content hash of the file
+/// bytes, and the parsed fields to reuse when the hash is unchanged.
+#[derive(Serialize, Deserialize, Clone)]
+struct CachedWorkspace {
+    path: PathBuf,
+    hash: String,
+    package_json: PackageJson,
+}
+
+/// A dependency-free content hash of a manifest's bytes, used to decide whether
+/// a cached entry can be reused. `DefaultHasher` is not stable across toolchain
+/// versions, so an entry written by a differently built binary may fail to
+/// match and its manifest is then simply re-parsed; the hash never leaves the
+/// machine.
+fn content_hash(bytes: &[u8]) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
 }
 
 #[derive(Hash, Eq, PartialEq)]
@@ -147,10 +559,24 @@ impl Workspace {
         }
     }
 
-    pub fn dependency_version(&self, name: &str) -> Option<&DependencyVersion> {
-        self.dependencies
-            .iter()
-            .find_map(|(n, v)| if n == name { Some(v) } else { None })
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn version(&self) -> Option<&Version> {
+        self.version.as_ref()
+    }
+}
+
+impl DependencyVersion {
+    /// Whether `version` satisfies this requirement. The `workspace:` protocol
+    /// carries an ordinary `VersionReq` once the alias is stripped, so both
+    /// variants defer to the same match.
+    pub fn matches(&self, version: &Version) -> bool {
+        match self {
+            DependencyVersion::VersionReq(req) => req.matches(version),
+            DependencyVersion::WorkspaceVersionReq(_, req) => req.matches(version),
+        }
     }
 }
 
@@ -164,14 +590,14 @@ impl FromStr for DependencyVersion {
     type Err = anyhow::Error;
 
     fn from_str(version: &str) -> Result<Self, Self::Err> {
-        if version.starts_with("workspace:") {
-            let parts: Vec<&str> = version.splitn(2, ':').collect();
-            if parts.len() == 2 {
-                let version_req = semver::VersionReq::parse(parts[1])?;
-                Ok(Self::WorkspaceVersionReq(parts[0].to_string(), version_req))
-            } else {
-                Err(anyhow::anyhow!("Invalid workspace version format"))
-            }
+        if let Some(rest) = version.strip_prefix("workspace:") {
+            // `workspace:`, `workspace:^`, and `workspace:~` are protocol
+            // aliases pnpm resolves to the current local version at publish
+            // time, same as the already-valid `workspace:*` — none of them
+            // parse as a `VersionReq` on their own, so normalize to `*`.
+            let rest = if matches!(rest, "" | "^" | "~") { "*" } else { rest };
+            let version_req = semver::VersionReq::parse(rest)?;
+            Ok(Self::WorkspaceVersionReq("workspace".to_string(), version_req))
         } else {
             let version_req = semver::VersionReq::parse(version)?;
             Ok(Self::VersionReq(version_req))
@@ -179,13 +605,13 @@ impl FromStr for DependencyVersion {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct PackageJson {
     name: Option<String>,
     version: Option<Version>,
 
     #[serde(default)]
-    workspaces: Vec<String>,
+    workspaces: Workspaces,
 
     #[serde(default)]
     dependencies: HashMap<String, String>,
@@ -197,19 +623,38 @@ pub struct PackageJson {
     peer_dependencies: HashMap<String, String>,
 }
 
-impl Default for PackageJson {
-    fn default() -> Self {
-        Self {
-            name: None,
-            version: None,
-            workspaces: vec![],
-            dependencies: HashMap::new(),
-            dev_dependencies: HashMap::new(),
-            peer_dependencies: HashMap::new(),
+/// The `workspaces` field, which appears as a bare glob array (npm/Yarn) or as
+/// an object with a `packages` list (Yarn's object form).
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Workspaces {
+    Array(Vec<String>),
+    Object { packages: Vec<String> },
+}
+
+impl Workspaces {
+    fn patterns(&self) -> &[String] {
+        match self {
+            Workspaces::Array(patterns) => patterns,
+            Workspaces::Object { packages } => packages,
         }
     }
 }
 
+impl Default for Workspaces {
+    fn default() -> Self {
+        Workspaces::Array(Vec::new())
+    }
+}
+
+/// A `pnpm-workspace.yaml`, whose `packages:` list is an alternate source of
+/// workspace globs.
+#[derive(Deserialize)]
+struct PnpmWorkspace {
+    #[serde(default)]
+    packages: Vec<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,7 +666,7 @@ mod tests {
         let package_json = PackageJson {
             name: Some("test-package".to_string()),
             version: Some(Version::new(1, 0, 0)),
-            workspaces: vec![],
+            workspaces: Workspaces::Array(vec![]),
             dependencies: HashMap::new(),
             dev_dependencies: HashMap::new(),
             peer_dependencies: HashMap::new(),
@@ -249,7 +694,7 @@ mod tests {
 
         setup_single_workspace(&directory)?;
 
-        let project = Project::new(&directory);
+        let project = Project::load_cached(&directory)?;
         let workspaces = project.get_workspaces();
 
         assert_eq!(workspaces.count(), 1,);
@@ -260,7 +705,7 @@ mod tests {
     fn setup_multiple_workspaces(path: impl AsRef<Path>) -> Result<()> {
         let path = path.as_ref();
         let package_json = PackageJson {
-            workspaces: vec!["packages/*".into()],
+            workspaces: Workspaces::Array(vec!["packages/*".into()]),
             ..Default::default()
         };
         let package_json_path = path.join("package.json");
@@ -274,7 +719,7 @@ mod tests {
             std::fs::create_dir_all(&package_path)?;
             let package_json_path = package_path.join("package.json");
             let package_json = PackageJson {
-                name: Some(format!("{}", package)),
+                name: Some(package.to_string()),
                 ..Default::default()
             };
             std::fs::write(&package_json_path, serde_json::to_string(&package_json)?)?;
@@ -290,10 +735,261 @@ mod tests {
 
         setup_multiple_workspaces(&directory)?;
 
-        let project = Project::new(&directory);
+        let project = Project::load_cached(&directory)?;
         let workspaces = project.get_workspaces();
         assert_eq!(workspaces.count(), 4);
 
         Ok(())
     }
+
+    #[test]
+    fn test_discovery_ignores_package_json_outside_workspaces_globs() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let directory = temp_dir.path().to_path_buf();
+
+        setup_multiple_workspaces(&directory)?;
+
+        // Neither of these is reachable through the root's `packages/*` glob,
+        // so they must not become release-candidate workspaces.
+        let vendor_dir = directory.join("vendor").join("unrelated");
+        std::fs::create_dir_all(&vendor_dir)?;
+        std::fs::write(
+            vendor_dir.join("package.json"),
+            serde_json::to_string(&PackageJson {
+                name: Some("unrelated".into()),
+                ..Default::default()
+            })?,
+        )?;
+
+        let nested_dir = directory
+            .join("packages")
+            .join("a")
+            .join("node_modules")
+            .join("nested");
+        std::fs::create_dir_all(&nested_dir)?;
+        std::fs::write(
+            nested_dir.join("package.json"),
+            serde_json::to_string(&PackageJson {
+                name: Some("nested".into()),
+                ..Default::default()
+            })?,
+        )?;
+
+        let project = Project::load_cached(&directory)?;
+        assert_eq!(project.get_workspaces().count(), 4);
+        assert!(project.get_workspace("unrelated").is_none());
+        assert!(project.get_workspace("nested").is_none());
+
+        Ok(())
+    }
+
+    fn setup_dependent_workspaces(path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let root = PackageJson {
+            workspaces: Workspaces::Array(vec!["packages/*".into()]),
+            ..Default::default()
+        };
+        std::fs::write(path.join("package.json"), serde_json::to_string(&root)?)?;
+
+        let a_dir = path.join("packages").join("a");
+        std::fs::create_dir_all(&a_dir)?;
+        let a = PackageJson {
+            name: Some("a".into()),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        };
+        std::fs::write(a_dir.join("package.json"), serde_json::to_string(&a)?)?;
+
+        let b_dir = path.join("packages").join("b");
+        std::fs::create_dir_all(&b_dir)?;
+        let b = PackageJson {
+            name: Some("b".into()),
+            version: Some(Version::new(1, 0, 0)),
+            dependencies: HashMap::from([("a".to_string(), "^1.0.0".to_string())]),
+            ..Default::default()
+        };
+        std::fs::write(b_dir.join("package.json"), serde_json::to_string(&b)?)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dependency_edges() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let directory = temp_dir.path().to_path_buf();
+
+        setup_dependent_workspaces(&directory)?;
+
+        let project = Project::load_cached(&directory)?;
+        let (a, _) = project.get_workspace("a").unwrap();
+        let (b, _) = project.get_workspace("b").unwrap();
+
+        // b depends on a, so the dependency relation links them...
+        assert_eq!(project.dependencies(b).collect::<Vec<_>>(), vec![a]);
+        assert_eq!(project.dependents(a).collect::<Vec<_>>(), vec![b]);
+        // ...while the containment relation does not.
+        assert!(project.dependencies(a).next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_affected_fans_out_through_dependents() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let directory = temp_dir.path().to_path_buf();
+
+        setup_dependent_workspaces(&directory)?;
+
+        let project = Project::load_cached(&directory)?;
+        let (a, _) = project.get_workspace("a").unwrap();
+        let (b, _) = project.get_workspace("b").unwrap();
+
+        // A change inside `a` also affects `b`, which depends on it.
+        let changed = vec![directory.join("packages").join("a").join("index.ts")];
+        let affected = project.affected(changed);
+        assert_eq!(affected, HashSet::from([a, b]));
+
+        // A path outside every workspace directory is ignored.
+        assert!(
+            project
+                .affected(vec![PathBuf::from("/somewhere/else/file.ts")])
+                .is_empty()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_cached_writes_and_reuses_cache() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let directory = temp_dir.path().to_path_buf();
+
+        setup_dependent_workspaces(&directory)?;
+
+        // First load populates the cache and discovers the root and both
+        // package workspaces.
+        let project = Project::load_cached(&directory)?;
+        assert_eq!(project.get_workspaces().count(), 3);
+        assert!(directory.join(".changement").join("graph.json").exists());
+
+        // A second load re-uses the cache and still resolves the graph.
+        let project = Project::load_cached(&directory)?;
+        assert!(project.get_workspace("a").is_some());
+        assert!(project.get_workspace("b").is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_range_preserves_operator_and_protocol() {
+        let version = Version::new(1, 3, 0);
+        assert_eq!(rewrite_range("^1.2.0", &version).as_deref(), Some("^1.3.0"));
+        assert_eq!(rewrite_range("~1.2.0", &version).as_deref(), Some("~1.3.0"));
+        assert_eq!(
+            rewrite_range("workspace:1.2.0", &version).as_deref(),
+            Some("workspace:1.3.0")
+        );
+        assert_eq!(
+            rewrite_range("workspace:^1.2.0", &version).as_deref(),
+            Some("workspace:^1.3.0")
+        );
+        // Protocol aliases resolve at publish time and are left alone.
+        assert_eq!(rewrite_range("workspace:*", &version), None);
+        assert_eq!(rewrite_range("workspace:^", &version), None);
+        assert_eq!(rewrite_range("*", &version), None);
+    }
+
+    #[test]
+    fn test_dependency_version_parses_bare_workspace_protocol_aliases() {
+        let version = Version::new(1, 0, 0);
+        for alias in ["workspace:", "workspace:^", "workspace:~", "workspace:*"] {
+            let parsed: DependencyVersion = alias
+                .parse()
+                .unwrap_or_else(|_| panic!("expected '{alias}' to parse as a workspace protocol alias"));
+            assert!(parsed.matches(&version));
+        }
+    }
+
+    #[test]
+    fn test_rewrite_range_leaves_compound_and_union_ranges_untouched() {
+        let version = Version::new(1, 3, 0);
+        // Their new form can't be derived from a single version, so they're
+        // left as-is rather than silently dropping part of the range.
+        assert_eq!(rewrite_range(">=1.2.0 <2.0.0", &version), None);
+        assert_eq!(rewrite_range("1.2.3 || 2.0.0", &version), None);
+        assert_eq!(rewrite_range("1.x", &version), None);
+    }
+
+    #[test]
+    fn test_rewrite_dependents() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let directory = temp_dir.path().to_path_buf();
+
+        setup_dependent_workspaces(&directory)?;
+
+        let project = Project::load_cached(&directory)?;
+        let (a, _) = project.get_workspace("a").unwrap();
+
+        let rewritten = project.rewrite_dependents(a, &Version::new(1, 1, 0))?;
+        assert_eq!(rewritten.len(), 1);
+
+        let b_manifest = directory.join("packages").join("b").join("package.json");
+        let value: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(b_manifest)?)?;
+        assert_eq!(value["dependencies"]["a"], "^1.1.0");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_version_preserves_key_order_and_indentation() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let directory = temp_dir.path().to_path_buf();
+
+        let manifest_path = directory.join("package.json");
+        std::fs::write(
+            &manifest_path,
+            "{\n    \"zzz_first_key\": true,\n    \"name\": \"a\",\n    \"version\": \"1.0.0\",\n    \"aaa_last_key\": false\n}\n",
+        )?;
+
+        let project = Project::load_cached(&directory)?;
+        let (a, _) = project.get_workspace("a").unwrap();
+
+        project.write_version(a, &Version::new(1, 1, 0))?;
+
+        let text = std::fs::read_to_string(&manifest_path)?;
+        assert_eq!(
+            text,
+            "{\n    \"zzz_first_key\": true,\n    \"name\": \"a\",\n    \"version\": \"1.1.0\",\n    \"aaa_last_key\": false\n}\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_release_order() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let directory = temp_dir.path().to_path_buf();
+
+        setup_multiple_workspaces(&directory)?;
+
+        let project = Project::load_cached(&directory)?;
+        let roots: Vec<NodeIndex> = project.get_workspaces().map(|(index, _)| index).collect();
+        let batches = project.release_order(roots)?;
+
+        // Every node is scheduled exactly once across the batches, and a
+        // workspace never shares a batch with one of its dependencies.
+        let scheduled: usize = batches.iter().map(|batch| batch.len()).sum();
+        assert_eq!(scheduled, 4);
+        for (wave, batch) in batches.iter().enumerate() {
+            for &node in batch {
+                for dependency in project.dependencies(node) {
+                    let earlier = batches[..wave].iter().any(|b| b.contains(&dependency));
+                    assert!(earlier, "dependency must be released in an earlier wave");
+                }
+            }
+        }
+
+        Ok(())
+    }
 }